@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use Value;
+
+/// Describes the format in which a configuration file's contents are stored
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FileFormat {
+    /// TOML (parsed with toml)
+    Toml,
+
+    /// JSON (parsed with serde_json)
+    Json,
+
+    /// YAML (parsed with yaml_rust)
+    Yaml,
+}
+
+/// Every file extension this crate recognizes, grouped by the format that
+/// handles it, in the order they should be probed when a format is not
+/// specified up front.
+static ALL_EXTENSIONS: &'static [(FileFormat, &'static [&'static str])] = &[
+    (FileFormat::Toml, &["toml"]),
+    (FileFormat::Json, &["json"]),
+    (FileFormat::Yaml, &["yaml", "yml"]),
+];
+
+impl FileFormat {
+    /// Extensions associated with this particular file format
+    pub(crate) fn extensions(&self) -> &'static [&'static str] {
+        for &(format, extensions) in ALL_EXTENSIONS {
+            if format == *self {
+                return extensions;
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Iterates every `(format, extension)` pair this crate knows how to
+    /// read, in registration order. Used to probe for a file whose format
+    /// was not specified up front.
+    pub(crate) fn all_extensions() -> impl Iterator<Item = (FileFormat, &'static str)> {
+        ALL_EXTENSIONS
+            .iter()
+            .flat_map(|&(format, extensions)| extensions.iter().map(move |ext| (format, *ext)))
+    }
+
+    /// Looks up the format registered for a given file extension, if any.
+    /// Comparison is case-insensitive since filesystems commonly are not.
+    pub(crate) fn from_extension(ext: &str) -> Option<FileFormat> {
+        FileFormat::all_extensions()
+            .find(|&(_, known)| known.eq_ignore_ascii_case(ext))
+            .map(|(format, _)| format)
+    }
+
+    /// Parses a file's contents into the raw key/value table that feeds a
+    /// [`Config`]. `uri` is the file's location, if known, and is only used
+    /// in the error returned when the format is not enabled; errors from the
+    /// format's own parser do not include it. `Value` has no `Deserialize` impl,
+    /// so each format's own document model is parsed first and then mapped
+    /// into `Value` by hand.
+    pub(crate) fn parse(&self,
+                         uri: Option<&String>,
+                         text: &str)
+                         -> Result<HashMap<String, Value>, Box<Error>> {
+        match *self {
+            #[cfg(feature = "toml")]
+            FileFormat::Toml => {
+                let root = text.parse::<toml::Value>().map_err(|e| Box::new(e) as Box<Error>)?;
+
+                into_table(from_toml_value(root))
+            }
+
+            #[cfg(feature = "json")]
+            FileFormat::Json => {
+                let root = serde_json::from_str(text).map_err(|e| Box::new(e) as Box<Error>)?;
+
+                into_table(from_json_value(root))
+            }
+
+            #[cfg(feature = "yaml")]
+            FileFormat::Yaml => {
+                let mut docs = yaml_rust::YamlLoader::load_from_str(text)
+                    .map_err(|e| Box::new(io_error(e.to_string())) as Box<Error>)?;
+                let root = docs.drain(..).next().unwrap_or(yaml_rust::Yaml::Hash(Default::default()));
+
+                into_table(from_yaml_value(root))
+            }
+
+            #[allow(unreachable_patterns)]
+            _ => {
+                Err(Box::new(io_error(format!("the {:?} format used by {:?} is not enabled",
+                                               self,
+                                               uri))))
+            }
+        }
+    }
+
+    /// Serializes a [`Value`] tree into this format's native document
+    /// representation. `Value` has no `Serialize` impl, so it is mapped
+    /// into each format's own document model by hand before handing off to
+    /// that format's writer.
+    pub(crate) fn serialize(&self, value: &Value) -> Result<String, Box<Error>> {
+        match *self {
+            #[cfg(feature = "toml")]
+            FileFormat::Toml => {
+                toml::to_string_pretty(&to_toml_value(value)).map_err(|e| Box::new(e) as Box<Error>)
+            }
+
+            #[cfg(feature = "json")]
+            FileFormat::Json => {
+                serde_json::to_string_pretty(&to_json_value(value))
+                    .map_err(|e| Box::new(e) as Box<Error>)
+            }
+
+            #[cfg(feature = "yaml")]
+            FileFormat::Yaml => {
+                let mut text = String::new();
+                yaml_rust::YamlEmitter::new(&mut text)
+                    .dump(&to_yaml_value(value))
+                    .map_err(|e| Box::new(io_error(e.to_string())) as Box<Error>)?;
+
+                Ok(text)
+            }
+
+            #[allow(unreachable_patterns)]
+            _ => Err(Box::new(io_error(format!("the {:?} format is not enabled", self)))),
+        }
+    }
+}
+
+fn io_error(message: String) -> ::std::io::Error {
+    ::std::io::Error::new(::std::io::ErrorKind::Other, message)
+}
+
+/// The document root of a configuration file is always a table; anything
+/// else is malformed input.
+fn into_table(value: Value) -> Result<HashMap<String, Value>, Box<Error>> {
+    match value {
+        Value::Table(table) => Ok(table),
+        _ => Err(Box::new(io_error("expected a table at the document root".into()))),
+    }
+}
+
+#[cfg(feature = "toml")]
+fn to_toml_value(value: &Value) -> toml::Value {
+    match *value {
+        Value::String(ref v) => toml::Value::String(v.clone()),
+        Value::Integer(v) => toml::Value::Integer(v),
+        Value::Float(v) => toml::Value::Float(v),
+        Value::Boolean(v) => toml::Value::Boolean(v),
+        Value::Array(ref arr) => toml::Value::Array(arr.iter().map(to_toml_value).collect()),
+        Value::Table(ref table) => {
+            toml::Value::Table(table.iter().map(|(k, v)| (k.clone(), to_toml_value(v))).collect())
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+fn from_toml_value(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(v) => Value::String(v),
+        toml::Value::Integer(v) => Value::Integer(v),
+        toml::Value::Float(v) => Value::Float(v),
+        toml::Value::Boolean(v) => Value::Boolean(v),
+        toml::Value::Datetime(v) => Value::String(v.to_string()),
+        toml::Value::Array(arr) => Value::Array(arr.into_iter().map(from_toml_value).collect()),
+        toml::Value::Table(table) => {
+            Value::Table(table.into_iter().map(|(k, v)| (k, from_toml_value(v))).collect())
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn to_json_value(value: &Value) -> serde_json::Value {
+    match *value {
+        Value::String(ref v) => serde_json::Value::String(v.clone()),
+        Value::Integer(v) => serde_json::Value::Number(v.into()),
+        Value::Float(v) => {
+            serde_json::Number::from_f64(v)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null)
+        }
+        Value::Boolean(v) => serde_json::Value::Bool(v),
+        Value::Array(ref arr) => serde_json::Value::Array(arr.iter().map(to_json_value).collect()),
+        Value::Table(ref table) => {
+            serde_json::Value::Object(table.iter().map(|(k, v)| (k.clone(), to_json_value(v))).collect())
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn from_json_value(value: serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::String(String::new()),
+        serde_json::Value::Bool(v) => Value::Boolean(v),
+        serde_json::Value::Number(ref v) if v.is_i64() => Value::Integer(v.as_i64().unwrap()),
+        serde_json::Value::Number(v) => Value::Float(v.as_f64().unwrap_or(0.0)),
+        serde_json::Value::String(v) => Value::String(v),
+        serde_json::Value::Array(arr) => Value::Array(arr.into_iter().map(from_json_value).collect()),
+        serde_json::Value::Object(obj) => {
+            Value::Table(obj.into_iter().map(|(k, v)| (k, from_json_value(v))).collect())
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn to_yaml_value(value: &Value) -> yaml_rust::Yaml {
+    use yaml_rust::Yaml;
+
+    match *value {
+        Value::String(ref v) => Yaml::String(v.clone()),
+        Value::Integer(v) => Yaml::Integer(v),
+        Value::Float(v) => Yaml::Real(v.to_string()),
+        Value::Boolean(v) => Yaml::Boolean(v),
+        Value::Array(ref arr) => Yaml::Array(arr.iter().map(to_yaml_value).collect()),
+        Value::Table(ref table) => {
+            let mut hash = yaml_rust::yaml::Hash::new();
+
+            for (k, v) in table {
+                hash.insert(Yaml::String(k.clone()), to_yaml_value(v));
+            }
+
+            Yaml::Hash(hash)
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn from_yaml_value(value: yaml_rust::Yaml) -> Value {
+    use yaml_rust::Yaml;
+
+    match value {
+        Yaml::Real(v) => Value::Float(v.parse().unwrap_or(0.0)),
+        Yaml::Integer(v) => Value::Integer(v),
+        Yaml::String(v) => Value::String(v),
+        Yaml::Boolean(v) => Value::Boolean(v),
+        Yaml::Array(arr) => Value::Array(arr.into_iter().map(from_yaml_value).collect()),
+        Yaml::Hash(hash) => {
+            Value::Table(hash.into_iter()
+                .filter_map(|(k, v)| match k {
+                    Yaml::String(k) => Some((k, from_yaml_value(v))),
+                    _ => None,
+                })
+                .collect())
+        }
+        Yaml::Null | Yaml::BadValue | Yaml::Alias(_) => Value::String(String::new()),
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use source::Source;
+use Config;
+use Value;
+
+mod format;
+pub mod source;
+
+pub use self::format::FileFormat;
+pub use self::source::{FileSearchPath, FileSource, FileSourceFile, FileSourceString};
+
+/// A configuration source backed up by a file
+#[derive(Debug, Clone)]
+pub struct File<T: FileSource> {
+    /// Format of file (which dictates what driver to use)
+    format: Option<FileFormat>,
+
+    /// A required File will error out if it cannot be found
+    required: bool,
+
+    source: T,
+}
+
+impl File<source::FileSourceFile> {
+    pub fn with_name(name: &str) -> Self {
+        File {
+            format: None,
+            required: true,
+            source: source::FileSourceFile::new(name),
+        }
+    }
+}
+
+impl File<source::FileSourceString> {
+    pub fn from_str(s: &str, format: FileFormat) -> Self {
+        File {
+            format: Some(format),
+            required: true,
+            source: source::FileSourceString::new(s),
+        }
+    }
+}
+
+impl<T: FileSource> File<T> {
+    pub fn format(mut self, format: FileFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+}
+
+impl<T: FileSource> Source for File<T> {
+    fn collect(&self) -> Result<HashMap<String, Value>, Box<Error>> {
+        // A file that is not required and cannot be resolved contributes
+        // nothing instead of failing the merge
+        let (uri, text, format) = match self.source.resolve(self.format) {
+            Ok(resolved) => resolved,
+            Err(_) if !self.required => return Ok(HashMap::new()),
+            Err(error) => return Err(error),
+        };
+
+        format.parse(uri.as_ref(), &text)
+    }
+}
+
+/// Writes a fully-merged [`Config`] out to `path` in the given
+/// [`FileFormat`], picking the extension from that format's registered
+/// extensions. Useful for generating a canonical config file on first run,
+/// or for snapshotting the effective configuration after overrides have
+/// been applied.
+///
+/// Returns the path that was actually written, with its extension set to
+/// match `format`.
+pub fn write(config: &Config, mut path: PathBuf, format: FileFormat) -> Result<PathBuf, Box<Error>> {
+    path.set_extension(format.extensions()[0]);
+
+    let text = format.serialize(&config.cache)?;
+    fs::write(&path, text)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::io::Read;
+
+    fn round_trip(original: &str, merged: &str, format: FileFormat) -> Config {
+        let mut config = Config::new();
+        config.merge(File::from_str(original, format)).unwrap();
+        config.merge(File::from_str(merged, format)).unwrap();
+
+        let path = env::temp_dir().join(format!("config-rs-round-trip-test-{:?}", format));
+        let written = write(&config, path, format).unwrap();
+
+        let mut text = String::new();
+        fs::File::open(&written).unwrap().read_to_string(&mut text).unwrap();
+        fs::remove_file(&written).unwrap();
+
+        let mut reloaded = Config::new();
+        reloaded.merge(File::from_str(&text, format)).unwrap();
+
+        assert_eq!(reloaded.cache, config.cache);
+
+        reloaded
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn round_trips_toml() {
+        let config = round_trip("debug = false\nport = 8080\n", "debug = true\n", FileFormat::Toml);
+
+        // The override took effect, and the untouched integer survived the
+        // round trip as an integer rather than decaying to a float/string
+        assert_eq!(config.get_bool("debug").unwrap(), true);
+        assert_eq!(config.get_int("port").unwrap(), 8080);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn round_trips_json() {
+        let config = round_trip(r#"{"debug": false, "port": 8080}"#,
+                                 r#"{"debug": true}"#,
+                                 FileFormat::Json);
+
+        assert_eq!(config.get_bool("debug").unwrap(), true);
+        assert_eq!(config.get_int("port").unwrap(), 8080);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn round_trips_yaml() {
+        let config = round_trip("debug: false\nport: 8080\n", "debug: true\n", FileFormat::Yaml);
+
+        assert_eq!(config.get_bool("debug").unwrap(), true);
+        assert_eq!(config.get_int("port").unwrap(), 8080);
+    }
+}
@@ -10,6 +10,26 @@ use std::env;
 use source::Source;
 use super::{FileFormat, FileSource};
 
+/// A single entry of a [`FileSourceFile`] search path, analogous to one
+/// element of a `RUST_PATH`-style lookup list.
+#[derive(Debug, Clone)]
+pub enum FileSearchPath {
+    /// A literal directory
+    Dir(String),
+
+    /// A directory taken from the named environment variable at lookup time
+    Env(String),
+}
+
+impl FileSearchPath {
+    fn resolve(&self) -> Result<PathBuf, Box<Error>> {
+        match *self {
+            FileSearchPath::Dir(ref dir) => Ok(PathBuf::from(dir)),
+            FileSearchPath::Env(ref key) => Ok(PathBuf::from(env::var(key)?)),
+        }
+    }
+}
+
 /// Describes a file sourced from a file
 pub struct FileSourceFile {
     /// Basename of configuration file
@@ -18,6 +38,15 @@ pub struct FileSourceFile {
     /// Directory where configuration file is found
     /// When not specified, the current working directory (CWD) is considered
     path: Option<String>,
+
+    /// An explicit, ordered list of directories to search instead of the
+    /// current directory. When set, `ancestor_lookup` has no effect: only
+    /// these directories are tried, in order, and the first hit wins.
+    search_path: Option<Vec<FileSearchPath>>,
+
+    /// Whether to walk up parent directories (git-style) when the file
+    /// isn't found in the starting directory. Defaults to `true`.
+    ancestor_lookup: bool,
 }
 
 impl FileSourceFile {
@@ -25,13 +54,86 @@ impl FileSourceFile {
         FileSourceFile {
             name: name.into(),
             path: None,
+            search_path: None,
+            ancestor_lookup: true,
+        }
+    }
+
+    /// Restricts the lookup to an explicit, ordered list of directories
+    /// instead of probing the current directory (and its ancestors).
+    pub fn search_path(mut self, search_path: Vec<FileSearchPath>) -> Self {
+        self.search_path = Some(search_path);
+        self
+    }
+
+    /// Enables or disables walking up parent directories when the file is
+    /// not found in the starting directory. Defaults to `true`.
+    pub fn ancestor_lookup(mut self, ancestor_lookup: bool) -> Self {
+        self.ancestor_lookup = ancestor_lookup;
+        self
+    }
+
+    /// Probes a single directory for `basename`, first as a complete,
+    /// existing path (e.g. "config/prod.toml", so a name that already
+    /// names a real file is honored as-is) and then under every candidate
+    /// extension. Exact matches are checked per-directory, alongside
+    /// extension probing, so an explicit `search_path` is consulted in
+    /// order rather than always deferring to whatever sits in the cwd.
+    fn probe_dir(&self,
+                 dir: &Path,
+                 basename: &Path,
+                 format_hint: Option<FileFormat>)
+                 -> Result<Option<(PathBuf, FileFormat)>, Box<Error>> {
+        let mut filename = dir.join(basename);
+
+        if filename.is_file() {
+            let format = match format_hint {
+                Some(format) => format,
+                None => {
+                    let ext = filename.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+
+                    match FileFormat::from_extension(ext) {
+                        Some(format) => format,
+                        None => {
+                            return Err(Box::new(io::Error::new(io::ErrorKind::NotFound,
+                                                     format!("file \"{}\" is not of a \
+                                                              registered file format",
+                                                             filename.to_string_lossy()))));
+                        }
+                    }
+                }
+            };
+
+            return Ok(Some((filename, format)));
+        }
+
+        if let Some(format) = format_hint {
+            // Only the requested format is a candidate
+            for ext in format.extensions() {
+                filename.set_extension(ext);
+
+                if filename.is_file() {
+                    return Ok(Some((filename, format)));
+                }
+            }
+        } else {
+            // No format was given; probe every known format/extension pair
+            // and take the first one that resolves to a real file
+            for (format, ext) in FileFormat::all_extensions() {
+                filename.set_extension(ext);
+
+                if filename.is_file() {
+                    return Ok(Some((filename, format)));
+                }
+            }
         }
+
+        Ok(None)
     }
 
-    fn find_file(&self, format_hint: Option<FileFormat>) -> Result<PathBuf, Box<Error>> {
+    fn find_file(&self, format_hint: Option<FileFormat>) -> Result<(PathBuf, FileFormat), Box<Error>> {
         // Build expected configuration file
         let mut basename = PathBuf::new();
-        let extensions = format_hint.unwrap().extensions();
 
         if let Some(ref path) = self.path {
             basename.push(path.clone());
@@ -39,22 +141,36 @@ impl FileSourceFile {
 
         basename.push(self.name.clone());
 
+        // An explicit search path takes over entirely: try each configured
+        // directory in order and stop at the first hit (exact match or
+        // extension probe), without walking up to any ancestors.
+        if let Some(ref search_path) = self.search_path {
+            for entry in search_path {
+                let dir = entry.resolve()?;
+
+                if let Some(found) = self.probe_dir(&dir, &basename, format_hint)? {
+                    return Ok(found);
+                }
+            }
+
+            return Err(Box::new(io::Error::new(io::ErrorKind::NotFound,
+                                        format!("configuration file \"{}\" not found in the \
+                                                 configured search path",
+                                                basename.to_string_lossy()))
+            ));
+        }
+
         // Find configuration file (algorithm similar to .git detection by git)
         let mut dir = env::current_dir()?;
-        let mut filename = dir.as_path().join(basename.clone());
 
         loop {
-            for ext in &extensions {
-                filename.set_extension(ext);
-
-                if filename.is_file() {
-                    // File exists and is a file
-                    return Ok(filename);
-                }
+            if let Some(found) = self.probe_dir(&dir, &basename, format_hint)? {
+                return Ok(found);
             }
 
-            // Not found.. travse up via the dir
-            if !dir.pop() {
+            // Not found.. travse up via the dir, unless ancestor lookup was
+            // disabled, in which case the starting directory is all we try
+            if !self.ancestor_lookup || !dir.pop() {
                 // Failed to find the configuration file
                 return Err(Box::new(io::Error::new(io::ErrorKind::NotFound,
                                             format!("configuration file \"{}\" not found",
@@ -66,9 +182,9 @@ impl FileSourceFile {
 }
 
 impl FileSource for FileSourceFile {
-    fn resolve(&self, format_hint: Option<FileFormat>) -> Result<(Option<String>, String), Box<Error>> {
+    fn resolve(&self, format_hint: Option<FileFormat>) -> Result<(Option<String>, String, FileFormat), Box<Error>> {
         // Find file
-        let filename = self.find_file(format_hint)?;
+        let (filename, format) = self.find_file(format_hint)?;
 
         // Attempt to use a relative path for the URI
         let base = env::current_dir()?;
@@ -82,7 +198,7 @@ impl FileSource for FileSourceFile {
         let mut text = String::new();
         file.read_to_string(&mut text)?;
 
-        Ok((Some(uri.to_string_lossy().into_owned()), text))
+        Ok((Some(uri.to_string_lossy().into_owned()), text, format))
     }
 }
 
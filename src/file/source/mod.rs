@@ -0,0 +1,22 @@
+use std::error::Error;
+use std::fmt::Debug;
+
+use super::FileFormat;
+
+mod file;
+mod string;
+
+pub use self::file::{FileSourceFile, FileSearchPath};
+pub use self::string::FileSourceString;
+
+/// Describes where the file is sourced
+pub trait FileSource: Debug {
+    /// Resolves the file's contents, returning its URI (if known), its
+    /// text, and the format it was found in. When `format_hint` is `None`
+    /// the implementation detects the format itself where it can (e.g.
+    /// from the file's extension); a source with nothing to detect it from,
+    /// such as a string, returns an error instead.
+    fn resolve(&self,
+               format_hint: Option<FileFormat>)
+               -> Result<(Option<String>, String, FileFormat), Box<Error>>;
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+use std::io;
+
+use super::{FileFormat, FileSource};
+
+/// Describes a file sourced from a string
+#[derive(Debug, Clone)]
+pub struct FileSourceString(String);
+
+impl FileSourceString {
+    pub fn new(s: &str) -> FileSourceString {
+        FileSourceString(s.into())
+    }
+}
+
+impl FileSource for FileSourceString {
+    fn resolve(&self, format_hint: Option<FileFormat>) -> Result<(Option<String>, String, FileFormat), Box<Error>> {
+        // A string has no extension to probe, so a format must be given
+        let format = format_hint.ok_or_else(|| {
+            Box::new(io::Error::new(io::ErrorKind::InvalidInput,
+                                     "a format must be specified when sourcing configuration \
+                                      from a string"))
+                as Box<Error>
+        })?;
+
+        Ok((None, self.0.clone(), format))
+    }
+}